@@ -0,0 +1,304 @@
+//! FPGA Bitstream Loader
+//!
+//! Parses a Xilinx configuration image — either a `.bit` container or a raw
+//! `.bin` payload — and streams it to the Zynq processor configuration
+//! access port (PCAP) so a fresh image can be pushed to the fabric without a
+//! full board reboot.
+
+use std::convert::TryInto;
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Zynq processor configuration access port device node
+pub const CONFIG_DEVICE: &str = "/dev/xdevcfg";
+
+/// `PROG_DONE` status file exposed by the `xdevcfg` driver
+const DONE_STATUS_PATH: &str = "/sys/devices/soc0/amba/f8007000.devcfg/prog_done";
+
+/// Dummy pad word preceding the sync word in a Xilinx configuration stream
+const DUMMY_PAD: u32 = 0xFFFF_FFFF;
+
+/// Xilinx bitstream sync word
+const SYNC_WORD: u32 = 0xAA99_5566;
+
+/// Length, in bytes, of the magic preamble at the start of a `.bit` file,
+/// before the first `a`/`b`/`c`/`d`/`e` tag-length record
+const BIT_PREAMBLE_LEN: usize = 13;
+
+/// Maximum time to wait for the config port to report `PROG_DONE`
+const DONE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Bitstream loader error types
+#[derive(Debug)]
+pub enum BitstreamError {
+    Io(io::Error),
+    InvalidHeader(&'static str),
+    SyncWordNotFound,
+    Timeout,
+}
+
+impl From<io::Error> for BitstreamError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl std::fmt::Display for BitstreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::InvalidHeader(msg) => write!(f, "Invalid .bit header: {}", msg),
+            Self::SyncWordNotFound => {
+                write!(f, "Could not find bitstream sync word in either byte order")
+            }
+            Self::Timeout => write!(f, "Timed out waiting for PROG_DONE"),
+        }
+    }
+}
+
+impl std::error::Error for BitstreamError {}
+
+/// A parsed configuration payload, byte-order-corrected and ready to stream
+/// to the config port
+pub struct Bitstream {
+    /// Configuration words in the order the config port expects
+    words: Vec<u32>,
+    /// Index into `words` of the dummy pad immediately preceding the sync
+    /// word, i.e. where a full reconfiguration should start streaming from
+    sync_offset: usize,
+}
+
+impl Bitstream {
+    /// Parse a `.bit` container or raw `.bin` image already read into memory
+    pub fn parse(data: &[u8]) -> Result<Self, BitstreamError> {
+        let payload = if looks_like_bit_container(data) {
+            parse_bit_container(data)?
+        } else {
+            data
+        };
+
+        let words = normalize_word_order(to_words(payload))?;
+        let sync_offset = find_sync_offset(&words).ok_or(BitstreamError::SyncWordNotFound)?;
+
+        Ok(Self { words, sync_offset })
+    }
+
+    /// Read and parse a bitstream file from disk
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, BitstreamError> {
+        let data = std::fs::read(path)?;
+        Self::parse(&data)
+    }
+}
+
+/// Detect the `.bit` container's magic preamble (length `0x0009`)
+fn looks_like_bit_container(data: &[u8]) -> bool {
+    data.len() > BIT_PREAMBLE_LEN && data[0] == 0x00 && data[1] == 0x09
+}
+
+/// Skip the `a`/`b`/`c`/`d` tag-length header records (design name, part,
+/// date, time) and return the raw configuration payload following the `e`
+/// key's 32-bit big-endian length
+fn parse_bit_container(data: &[u8]) -> Result<&[u8], BitstreamError> {
+    let mut cursor = BIT_PREAMBLE_LEN;
+
+    loop {
+        let key = *data
+            .get(cursor)
+            .ok_or(BitstreamError::InvalidHeader("truncated record"))?;
+        cursor += 1;
+
+        if key == b'e' {
+            let len = read_u32_be(data, cursor)?;
+            cursor += 4;
+            // `len` comes straight from the file and can be up to u32::MAX,
+            // which would overflow `usize` on a 32-bit target before the
+            // slice bounds check below ever runs — check it explicitly so a
+            // corrupt length yields the existing error instead of a panic.
+            let end = cursor
+                .checked_add(len)
+                .ok_or(BitstreamError::InvalidHeader("truncated payload"))?;
+            return data
+                .get(cursor..end)
+                .ok_or(BitstreamError::InvalidHeader("truncated payload"));
+        }
+
+        if !matches!(key, b'a' | b'b' | b'c' | b'd') {
+            return Err(BitstreamError::InvalidHeader("unexpected tag in .bit header"));
+        }
+
+        let len = data
+            .get(cursor..cursor + 2)
+            .map(|b| u16::from_be_bytes(b.try_into().unwrap()) as usize)
+            .ok_or(BitstreamError::InvalidHeader("truncated length"))?;
+        cursor += 2 + len;
+    }
+}
+
+fn read_u32_be(data: &[u8], at: usize) -> Result<usize, BitstreamError> {
+    data.get(at..at + 4)
+        .map(|b| u32::from_be_bytes(b.try_into().unwrap()) as usize)
+        .ok_or(BitstreamError::InvalidHeader("truncated length"))
+}
+
+/// Pack the payload into 32-bit big-endian words
+fn to_words(payload: &[u8]) -> Vec<u32> {
+    payload
+        .chunks_exact(4)
+        .map(|c| u32::from_be_bytes(c.try_into().unwrap()))
+        .collect()
+}
+
+/// Detect whether the payload needs byte-swapping by scanning for the dummy
+/// pad + sync pattern in either order, and correct it if so
+fn normalize_word_order(words: Vec<u32>) -> Result<Vec<u32>, BitstreamError> {
+    if find_sync_offset(&words).is_some() {
+        return Ok(words);
+    }
+
+    let swapped: Vec<u32> = words.iter().map(|w| w.swap_bytes()).collect();
+    if find_sync_offset(&swapped).is_some() {
+        return Ok(swapped);
+    }
+
+    Err(BitstreamError::SyncWordNotFound)
+}
+
+fn find_sync_offset(words: &[u32]) -> Option<usize> {
+    words
+        .windows(2)
+        .position(|w| w[0] == DUMMY_PAD && w[1] == SYNC_WORD)
+}
+
+/// Zynq PCAP configuration port
+pub struct ConfigPort {
+    file: std::fs::File,
+}
+
+impl ConfigPort {
+    /// Open the standard `/dev/xdevcfg` config port
+    pub fn open() -> Result<Self, BitstreamError> {
+        Self::with_device(CONFIG_DEVICE)
+    }
+
+    /// Open a config port at a custom path (for testing)
+    pub fn with_device(device: &str) -> Result<Self, BitstreamError> {
+        let file = OpenOptions::new().write(true).open(device)?;
+        Ok(Self { file })
+    }
+
+    /// Stream a parsed bitstream to the config port, reporting progress via
+    /// `on_progress(words_written, total_words)`.
+    ///
+    /// When `partial` is set, the full-device init/sync preamble (the dummy
+    /// pad and sync word) is skipped and only the frame data after it is
+    /// written, for a partial reconfiguration onto an already-configured
+    /// device.
+    pub fn write_bitstream(
+        &mut self,
+        bitstream: &Bitstream,
+        partial: bool,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<(), BitstreamError> {
+        let start = if partial {
+            bitstream.sync_offset + 2
+        } else {
+            bitstream.sync_offset
+        };
+        let frame = &bitstream.words[start..];
+
+        for (i, word) in frame.iter().enumerate() {
+            self.file.write_all(&word.to_be_bytes())?;
+            on_progress(i + 1, frame.len());
+        }
+
+        self.wait_for_done()
+    }
+
+    /// Poll `PROG_DONE` until it reports completion or `DONE_TIMEOUT`
+    /// elapses
+    fn wait_for_done(&self) -> Result<(), BitstreamError> {
+        let started = Instant::now();
+        loop {
+            if read_prog_done()? {
+                return Ok(());
+            }
+            if started.elapsed() > DONE_TIMEOUT {
+                return Err(BitstreamError::Timeout);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+fn read_prog_done() -> Result<bool, BitstreamError> {
+    let mut status = String::new();
+    std::fs::File::open(DONE_STATUS_PATH)?.read_to_string(&mut status)?;
+    Ok(status.trim() == "1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bit_container(payload: &[u8]) -> Vec<u8> {
+        let mut data = vec![0x00, 0x09];
+        data.extend_from_slice(&[0u8; BIT_PREAMBLE_LEN - 2]);
+
+        for (key, value) in [(b'a', b"design".as_slice()), (b'b', b"part".as_slice())] {
+            data.push(key);
+            data.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            data.extend_from_slice(value);
+        }
+
+        data.push(b'e');
+        data.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        data.extend_from_slice(payload);
+        data
+    }
+
+    #[test]
+    fn test_parse_bit_container_extracts_payload() {
+        let mut payload = DUMMY_PAD.to_be_bytes().to_vec();
+        payload.extend_from_slice(&SYNC_WORD.to_be_bytes());
+        payload.extend_from_slice(&0xDEAD_BEEFu32.to_be_bytes());
+
+        let bitstream = Bitstream::parse(&bit_container(&payload)).unwrap();
+        assert_eq!(bitstream.sync_offset, 0);
+        assert_eq!(bitstream.words, vec![DUMMY_PAD, SYNC_WORD, 0xDEAD_BEEF]);
+    }
+
+    #[test]
+    fn test_parse_raw_bin_detects_byte_swap() {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&DUMMY_PAD.swap_bytes().to_be_bytes());
+        payload.extend_from_slice(&SYNC_WORD.swap_bytes().to_be_bytes());
+
+        let bitstream = Bitstream::parse(&payload).unwrap();
+        assert_eq!(bitstream.words, vec![DUMMY_PAD, SYNC_WORD]);
+    }
+
+    #[test]
+    fn test_missing_sync_word_is_an_error() {
+        let payload = vec![0u8; 16];
+        assert!(matches!(
+            Bitstream::parse(&payload),
+            Err(BitstreamError::SyncWordNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_corrupt_payload_length_is_an_error_not_an_overflow_panic() {
+        let mut data = vec![0x00, 0x09];
+        data.extend_from_slice(&[0u8; BIT_PREAMBLE_LEN - 2]);
+        data.push(b'e');
+        data.extend_from_slice(&u32::MAX.to_be_bytes());
+
+        assert!(matches!(
+            parse_bit_container(&data),
+            Err(BitstreamError::InvalidHeader(_))
+        ));
+    }
+}
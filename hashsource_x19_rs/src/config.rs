@@ -0,0 +1,328 @@
+//! Persistent Miner Configuration
+//!
+//! Stores fan targets, PID gains, and safety thresholds across reboots in a
+//! small versioned blob, mirroring the config-region pattern used for other
+//! settings on Zynq-based control boards: a magic/version/length header
+//! followed by the serialized fields, written to a temp file and renamed
+//! into place so a crash mid-write can never leave a corrupt active config.
+
+use crate::fpga::FpgaController;
+use crate::thermal::FanControllerConfig;
+use std::convert::TryInto;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default location of the persisted config blob
+pub const CONFIG_PATH: &str = "/config/miner_config.bin";
+
+/// Magic bytes identifying a valid config blob
+const MAGIC: [u8; 4] = *b"HSX1";
+
+/// Bumped whenever [`MinerConfig`]'s on-disk layout changes, so a future
+/// version can detect and migrate an older blob instead of misreading it
+const CONFIG_VERSION: u16 = 1;
+
+/// Config store error types
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    Truncated,
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::InvalidMagic => write!(f, "Not a miner config blob"),
+            Self::UnsupportedVersion(v) => write!(f, "Unsupported config version: {}", v),
+            Self::Truncated => write!(f, "Config blob is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Persisted fan/thermal tuning, mirrored into [`crate::thermal::FanControllerConfig`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinerConfig {
+    pub target_temp_c: f32,
+    pub kp: f32,
+    pub ki: f32,
+    pub kd: f32,
+    pub min_duty_percent: u8,
+    pub max_duty_percent: u8,
+    pub trip_temp_c: f32,
+    pub sample_interval_secs: u32,
+    /// Per-channel duty override; `None` means follow the PID output
+    pub channel_overrides: [Option<u8>; FpgaController::FAN_CHANNELS],
+}
+
+impl Default for MinerConfig {
+    fn default() -> Self {
+        let defaults = FanControllerConfig::default();
+        Self {
+            target_temp_c: defaults.target_temp_c,
+            kp: defaults.kp,
+            ki: defaults.ki,
+            kd: defaults.kd,
+            min_duty_percent: defaults.min_duty_percent,
+            max_duty_percent: defaults.max_duty_percent,
+            trip_temp_c: defaults.trip_temp_c,
+            sample_interval_secs: defaults.sample_interval.as_secs() as u32,
+            channel_overrides: [None; FpgaController::FAN_CHANNELS],
+        }
+    }
+}
+
+impl MinerConfig {
+    /// Convert to the runtime config [`crate::thermal::FanController`] consumes
+    pub fn to_fan_config(self) -> FanControllerConfig {
+        FanControllerConfig {
+            target_temp_c: self.target_temp_c,
+            kp: self.kp,
+            ki: self.ki,
+            kd: self.kd,
+            min_duty_percent: self.min_duty_percent,
+            max_duty_percent: self.max_duty_percent,
+            trip_temp_c: self.trip_temp_c,
+            sample_interval: Duration::from_secs(self.sample_interval_secs as u64),
+            channel_overrides: self.channel_overrides,
+        }
+    }
+
+    /// Load the persisted config from [`CONFIG_PATH`]
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(CONFIG_PATH)
+    }
+
+    /// Load the persisted config from a custom path (for testing)
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let blob = fs::read(path)?;
+        decode_blob(&blob)
+    }
+
+    /// Atomically persist this config to [`CONFIG_PATH`]
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(CONFIG_PATH)
+    }
+
+    /// Atomically persist this config to a custom path (for testing)
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let blob = encode_blob(self);
+
+        // Write to a sibling temp file, then rename into place: on the same
+        // filesystem `rename` is atomic, so a crash mid-write leaves either
+        // the old config or the new one, never a half-written blob.
+        let tmp_path = tmp_path_for(path);
+        fs::write(&tmp_path, &blob)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Delete the persisted config (a later [`Self::load`] will fail until
+    /// something saves a new one)
+    pub fn remove() -> Result<(), ConfigError> {
+        Self::remove_from(CONFIG_PATH)
+    }
+
+    /// Delete the persisted config at a custom path (for testing)
+    pub fn remove_from(path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Reset the persisted config to defaults
+    pub fn erase() -> Result<(), ConfigError> {
+        Self::default().save()
+    }
+}
+
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
+
+fn encode_blob(config: &MinerConfig) -> Vec<u8> {
+    let body = encode_body(config);
+
+    let mut blob = Vec::with_capacity(4 + 2 + 4 + body.len());
+    blob.extend_from_slice(&MAGIC);
+    blob.extend_from_slice(&CONFIG_VERSION.to_be_bytes());
+    blob.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    blob.extend_from_slice(&body);
+    blob
+}
+
+fn decode_blob(blob: &[u8]) -> Result<MinerConfig, ConfigError> {
+    let magic = blob.get(0..4).ok_or(ConfigError::Truncated)?;
+    if magic != MAGIC {
+        return Err(ConfigError::InvalidMagic);
+    }
+
+    let version = blob
+        .get(4..6)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or(ConfigError::Truncated)?;
+    if version != CONFIG_VERSION {
+        return Err(ConfigError::UnsupportedVersion(version));
+    }
+
+    let body_len = blob
+        .get(6..10)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or(ConfigError::Truncated)?;
+    // `body_len` comes straight from a blob that may have been truncated or
+    // corrupted on flash, and can be up to u32::MAX — check the addition
+    // explicitly so a bad length yields `Truncated` instead of overflowing
+    // `usize` on a 32-bit target before the slice bounds check runs.
+    let body_end = 10usize.checked_add(body_len).ok_or(ConfigError::Truncated)?;
+    let body = blob.get(10..body_end).ok_or(ConfigError::Truncated)?;
+
+    decode_body(body)
+}
+
+fn encode_body(config: &MinerConfig) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&config.target_temp_c.to_be_bytes());
+    body.extend_from_slice(&config.kp.to_be_bytes());
+    body.extend_from_slice(&config.ki.to_be_bytes());
+    body.extend_from_slice(&config.kd.to_be_bytes());
+    body.push(config.min_duty_percent);
+    body.push(config.max_duty_percent);
+    body.extend_from_slice(&config.trip_temp_c.to_be_bytes());
+    body.extend_from_slice(&config.sample_interval_secs.to_be_bytes());
+    for overrride in &config.channel_overrides {
+        match overrride {
+            Some(v) => body.extend_from_slice(&[1, *v]),
+            None => body.extend_from_slice(&[0, 0]),
+        }
+    }
+    body
+}
+
+fn decode_body(body: &[u8]) -> Result<MinerConfig, ConfigError> {
+    let mut reader = ByteReader::new(body);
+    let target_temp_c = reader.f32()?;
+    let kp = reader.f32()?;
+    let ki = reader.f32()?;
+    let kd = reader.f32()?;
+    let min_duty_percent = reader.u8()?;
+    let max_duty_percent = reader.u8()?;
+    let trip_temp_c = reader.f32()?;
+    let sample_interval_secs = reader.u32()?;
+
+    let mut channel_overrides = [None; FpgaController::FAN_CHANNELS];
+    for slot in &mut channel_overrides {
+        let present = reader.u8()?;
+        let value = reader.u8()?;
+        *slot = (present != 0).then_some(value);
+    }
+
+    Ok(MinerConfig {
+        target_temp_c,
+        kp,
+        ki,
+        kd,
+        min_duty_percent,
+        max_duty_percent,
+        trip_temp_c,
+        sample_interval_secs,
+        channel_overrides,
+    })
+}
+
+/// Small cursor over a byte slice, used to decode the fixed-layout body
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ConfigError> {
+        let slice = self.data.get(self.pos..self.pos + n).ok_or(ConfigError::Truncated)?;
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, ConfigError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, ConfigError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, ConfigError> {
+        Ok(f32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_through_blob_encoding() {
+        let mut channel_overrides = [None; FpgaController::FAN_CHANNELS];
+        channel_overrides[0] = Some(42);
+        let config = MinerConfig {
+            target_temp_c: 70.5,
+            channel_overrides,
+            ..MinerConfig::default()
+        };
+
+        let blob = encode_blob(&config);
+        let decoded = decode_blob(&blob).unwrap();
+        assert_eq!(decoded, config);
+    }
+
+    #[test]
+    fn test_rejects_bad_magic() {
+        let blob = vec![0u8; 16];
+        assert!(matches!(decode_blob(&blob), Err(ConfigError::InvalidMagic)));
+    }
+
+    #[test]
+    fn test_corrupt_body_length_is_truncated_not_an_overflow_panic() {
+        let mut blob = Vec::new();
+        blob.extend_from_slice(&MAGIC);
+        blob.extend_from_slice(&CONFIG_VERSION.to_be_bytes());
+        blob.extend_from_slice(&u32::MAX.to_be_bytes());
+
+        assert!(matches!(decode_blob(&blob), Err(ConfigError::Truncated)));
+    }
+
+    #[test]
+    fn test_save_load_remove_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "hashsource_config_test_{:?}.bin",
+            std::thread::current().id()
+        ));
+
+        let config = MinerConfig::default();
+        config.save_to(&path).unwrap();
+        assert_eq!(MinerConfig::load_from(&path).unwrap(), config);
+
+        MinerConfig::remove_from(&path).unwrap();
+        assert!(MinerConfig::load_from(&path).is_err());
+    }
+}
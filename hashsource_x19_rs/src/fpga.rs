@@ -36,6 +36,12 @@ pub mod regs {
     /// Initialization config
     pub const INIT_CFG: usize = 0x088;
 
+    /// Tachometer count, main channel (gated edge count, see `TACH_GATE_SECS`)
+    pub const TACH_MAIN: usize = 0x08C;
+
+    /// Tachometer count, alternate channel
+    pub const TACH_ALT: usize = 0x090;
+
     /// PWM alternate channel
     pub const PWM_ALT: usize = 0x0A0;
 }
@@ -150,6 +156,48 @@ impl FpgaController {
         }
     }
 
+    /// Read register value at byte offset, without panicking on bad input
+    ///
+    /// Prefer this over [`Self::read_reg`] for any offset that is not known
+    /// at compile time (e.g. one computed from user input or a config file).
+    #[inline]
+    pub fn try_read_reg(&self, offset: usize) -> Result<u32, FpgaError> {
+        if !offset.is_multiple_of(4) || offset >= FPGA_SIZE {
+            return Err(FpgaError::InvalidOffset);
+        }
+
+        Ok(unsafe { ptr::read_volatile(self.regs.as_ptr().add(offset / 4)) })
+    }
+
+    /// Write register value at byte offset, without panicking on bad input
+    ///
+    /// Prefer this over [`Self::write_reg`] for any offset that is not known
+    /// at compile time.
+    #[inline]
+    pub fn try_write_reg(&mut self, offset: usize, value: u32) -> Result<(), FpgaError> {
+        if !offset.is_multiple_of(4) || offset >= FPGA_SIZE {
+            return Err(FpgaError::InvalidOffset);
+        }
+
+        unsafe {
+            ptr::write_volatile(self.regs.as_ptr().add(offset / 4), value);
+            // Memory barrier for ARM-FPGA coherency
+            fence(Ordering::SeqCst);
+        }
+
+        Ok(())
+    }
+
+    /// Typed, field-level view of [`regs::CTRL`]
+    pub fn ctrl(&mut self) -> crate::register::CtrlField<'_> {
+        crate::register::CtrlField(self)
+    }
+
+    /// Typed, field-level view of [`regs::INIT_CTRL`]
+    pub fn init_ctrl(&mut self) -> crate::register::InitCtrlField<'_> {
+        crate::register::InitCtrlField(self)
+    }
+
     /// Perform FPGA initialization sequence
     ///
     /// This is the stock firmware initialization sequence reverse-engineered
@@ -168,16 +216,18 @@ impl FpgaController {
         println!("Stage 1: Boot-time initialization");
 
         // Set bit 30 in register 0 (BM1391 init)
-        let reg0 = self.read_reg(regs::CTRL);
-        if reg0 & 0x4000_0000 == 0 {
-            self.write_reg(regs::CTRL, reg0 | 0x4000_0000);
+        if !self.ctrl().bm1391_init().is_set().unwrap_or(false) {
+            self.ctrl().bm1391_init().set()?;
             thread::sleep(Duration::from_millis(100));
             println!(
                 "  Set 0x000 = 0x{:08X} (bit 30 set)",
                 self.read_reg(regs::CTRL)
             );
         } else {
-            println!("  0x000 = 0x{:08X} (already correct)", reg0);
+            println!(
+                "  0x000 = 0x{:08X} (already correct)",
+                self.read_reg(regs::CTRL)
+            );
         }
 
         self.write_reg(regs::INIT_CTRL, 0x0080_800F);
@@ -191,7 +241,7 @@ impl FpgaController {
         // Stage 2: Bmminer startup sequence
         println!("Stage 2: Bmminer startup sequence");
 
-        self.write_reg(regs::INIT_CTRL, 0x8080_800F);
+        self.init_ctrl().startup_strobe().set()?;
         thread::sleep(Duration::from_millis(50));
         println!(
             "  Set 0x080 = 0x{:08X} (bit 31 set)",
@@ -202,7 +252,7 @@ impl FpgaController {
         thread::sleep(Duration::from_millis(50));
         println!("  Set 0x088 = 0x{:08X}", self.read_reg(regs::INIT_CFG));
 
-        self.write_reg(regs::INIT_CTRL, 0x0080_800F);
+        self.init_ctrl().startup_strobe().clear()?;
         thread::sleep(Duration::from_millis(50));
         println!(
             "  Set 0x080 = 0x{:08X} (bit 31 clear)",
@@ -221,20 +271,97 @@ impl FpgaController {
         Ok(())
     }
 
-    /// Set fan speed (0-100%)
+    /// Set fan speed (0-100%), applied identically to every fan channel
+    pub fn set_fan_speed(&mut self, percent: u8) {
+        for index in 0..Self::FAN_CHANNELS {
+            // Channel index is always in range, so this can't fail.
+            self.set_channel_duty(index, percent).ok();
+        }
+    }
+
+    /// Set the duty cycle (0-100%) for a single fan channel (0 = main, 1 = alt)
     ///
     /// Uses stock firmware PWM format: `(percent << 16) | (100 - percent)`
-    pub fn set_fan_speed(&mut self, percent: u8) {
-        let percent = percent.min(100) as u32;
+    pub fn set_channel_duty(&mut self, index: usize, percent: u8) -> Result<(), FpgaError> {
+        let offset = match index {
+            0 => regs::PWM_MAIN,
+            1 => regs::PWM_ALT,
+            _ => return Err(FpgaError::InvalidOffset),
+        };
 
-        // Stock firmware format
+        let percent = percent.min(100) as u32;
         let pwm_value = (percent << 16) | (100 - percent);
+        self.try_write_reg(offset, pwm_value)
+    }
 
-        self.write_reg(regs::PWM_MAIN, pwm_value);
-        self.write_reg(regs::PWM_ALT, pwm_value);
+    /// Read the raw tachometer edge count for fan channel `index` (0 = main,
+    /// 1 = alt), gated over `TACH_GATE_SECS`
+    pub fn fan_tach_count(&self, index: usize) -> Result<u32, FpgaError> {
+        let offset = match index {
+            0 => regs::TACH_MAIN,
+            1 => regs::TACH_ALT,
+            _ => return Err(FpgaError::InvalidOffset),
+        };
+        self.try_read_reg(offset)
+    }
+
+    /// Measured RPM for fan channel `index`, converted from the gated
+    /// tachometer edge count (`FAN_PULSES_PER_REV` pulses per revolution,
+    /// gated over `TACH_GATE_SECS` seconds)
+    ///
+    /// Widens to `u64` before scaling so a stuck or not-yet-latched
+    /// tachometer register reading near `u32::MAX` can't overflow the
+    /// conversion; `u32::MAX` itself is an implausible RPM and is reported
+    /// as such rather than silently wrapping.
+    pub fn fan_rpm(&self, index: usize) -> Result<u32, FpgaError> {
+        let count = self.fan_tach_count(index)? as u64;
+        let rpm = count * 60 / (FAN_PULSES_PER_REV as u64 * TACH_GATE_SECS as u64);
+        Ok(rpm.min(u32::MAX as u64) as u32)
+    }
+
+    /// Number of fan/tachometer channels the FPGA exposes
+    pub const FAN_CHANNELS: usize = 2;
+
+    /// Flag fan channels that are commanded to spin but are reporting no
+    /// (or near-zero) RPM, so the thermal controller can react to a failed
+    /// fan instead of continuing to trust a commanded duty cycle that never
+    /// reached the blades. `commanded_duty_percent` is per-channel, so a
+    /// channel intentionally parked at 0% (e.g. via
+    /// `FanControllerConfig::channel_overrides`) is never misreported as
+    /// stalled.
+    pub fn detect_stall(
+        &self,
+        commanded_duty_percent: [u8; Self::FAN_CHANNELS],
+    ) -> Result<Vec<usize>, FpgaError> {
+        let mut stalled = Vec::new();
+
+        for (index, &commanded) in commanded_duty_percent.iter().enumerate() {
+            let rpm = self.fan_rpm(index)?;
+            if is_stalled(commanded, rpm) {
+                stalled.push(index);
+            }
+        }
+
+        Ok(stalled)
     }
 }
 
+/// Core stall decision for a single channel, split out from [`FpgaController::detect_stall`]
+/// so it can be unit tested without a live FPGA handle
+fn is_stalled(commanded_duty_percent: u8, rpm: u32) -> bool {
+    commanded_duty_percent != 0 && rpm <= STALL_RPM_THRESHOLD
+}
+
+/// Tachometer pulses per fan revolution
+const FAN_PULSES_PER_REV: u32 = 2;
+
+/// Tachometer gate window, in seconds, over which edge counts accumulate
+/// before being latched into `TACH_MAIN`/`TACH_ALT`
+const TACH_GATE_SECS: u32 = 1;
+
+/// RPM at or below which a commanded-on fan is considered stalled
+const STALL_RPM_THRESHOLD: u32 = 300;
+
 impl Drop for FpgaController {
     fn drop(&mut self) {
         unsafe {
@@ -256,4 +383,39 @@ mod tests {
         let pwm = (percent << 16) | (100 - percent);
         assert_eq!(pwm, 0x0032_0032);
     }
+
+    #[test]
+    fn test_rpm_conversion() {
+        // 2 pulses/rev, 1 second gate: 66 edges -> 33 rev/s -> 1980 RPM
+        let count = 66u32;
+        let rpm = count * 60 / (super::FAN_PULSES_PER_REV * super::TACH_GATE_SECS);
+        assert_eq!(rpm, 1980);
+    }
+
+    #[test]
+    fn test_rpm_conversion_does_not_overflow_on_a_stuck_register() {
+        // A stuck/not-yet-latched tachometer register near u32::MAX must
+        // saturate to u32::MAX (an implausible RPM) instead of panicking or
+        // wrapping in the u32 * 60 multiply.
+        let count = u64::from(u32::MAX);
+        let rpm = (count * 60 / (u64::from(super::FAN_PULSES_PER_REV) * u64::from(super::TACH_GATE_SECS)))
+            .min(u64::from(u32::MAX)) as u32;
+        assert_eq!(rpm, u32::MAX);
+    }
+
+    #[test]
+    fn test_is_stalled_ignores_a_channel_intentionally_parked_at_zero() {
+        assert!(!super::is_stalled(0, 0));
+    }
+
+    #[test]
+    fn test_is_stalled_flags_commanded_channel_with_no_rpm() {
+        assert!(super::is_stalled(50, 0));
+    }
+
+    #[test]
+    fn test_is_stalled_respects_threshold() {
+        assert!(super::is_stalled(50, super::STALL_RPM_THRESHOLD));
+        assert!(!super::is_stalled(50, super::STALL_RPM_THRESHOLD + 1));
+    }
 }
@@ -0,0 +1,189 @@
+//! Hash-on-Plug Hotplug Detection
+//!
+//! Background monitor that polls and debounces `regs::HASH_ON_PLUG`'s
+//! per-slot presence bits and emits insert/remove events, so a supervising
+//! daemon can re-run the relevant init stages for a newly plugged hashboard
+//! or safely quiesce fans/PSU for a removed one, instead of requiring a
+//! full restart.
+
+use crate::fpga::{regs, FpgaController};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Number of hashboard slots tracked via `HASH_ON_PLUG`
+pub const SLOT_COUNT: usize = 3;
+
+/// How long a presence bit must hold steady before it's treated as real,
+/// rather than connector bounce while a board is being seated/removed
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Delay between presence polls
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A hotplug event for a single hashboard slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotplugEvent {
+    SlotInserted(usize),
+    SlotRemoved(usize),
+}
+
+/// A cheaply cloneable handle that reads the raw `HASH_ON_PLUG` value
+///
+/// The monitor only ever needs to read this one register, so it takes a
+/// handle to just that instead of owning the `FpgaController`. That keeps
+/// `&mut` access to the controller available to the rest of the program
+/// (for `initialize`, `set_fan_speed`, etc.) the whole time the monitor is
+/// running — taking the controller itself (even behind an `Arc`) would
+/// make `Arc::get_mut` permanently unreachable once the monitor thread
+/// held its own clone.
+pub type PresenceReader = Arc<dyn Fn() -> u32 + Send + Sync>;
+
+/// Build a [`PresenceReader`] from a controller shared behind a `Mutex`,
+/// the usual way to keep it mutably accessible elsewhere while the monitor
+/// thread reads from it
+pub fn presence_reader(fpga: Arc<Mutex<FpgaController>>) -> PresenceReader {
+    Arc::new(move || fpga.lock().unwrap().read_reg(regs::HASH_ON_PLUG))
+}
+
+/// Background monitor for hashboard presence
+///
+/// Dropping (or calling [`Self::stop`] on) the monitor signals its thread to
+/// exit and joins it.
+pub struct HotplugMonitor {
+    handle: Option<JoinHandle<()>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl HotplugMonitor {
+    /// Start monitoring for hotplug events via `read_presence`, delivered on
+    /// the returned channel. Boards already seated at the time this is
+    /// called are enumerated into the initial occupancy state, not reported
+    /// as insert events — only presence changes seen after startup are.
+    pub fn spawn(read_presence: PresenceReader) -> (Self, Receiver<HotplugEvent>) {
+        let (tx, rx) = mpsc::channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let thread_shutdown = Arc::clone(&shutdown);
+
+        let handle =
+            thread::spawn(move || monitor_loop(read_presence.as_ref(), &tx, &thread_shutdown));
+
+        (
+            Self {
+                handle: Some(handle),
+                shutdown,
+            },
+            rx,
+        )
+    }
+
+    /// Signal the monitor thread to exit and wait for it to stop
+    pub fn stop(mut self) {
+        self.stop_mut();
+    }
+
+    fn stop_mut(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for HotplugMonitor {
+    fn drop(&mut self) {
+        self.stop_mut();
+    }
+}
+
+fn monitor_loop(
+    read_presence: &(dyn Fn() -> u32 + Send + Sync),
+    tx: &Sender<HotplugEvent>,
+    shutdown: &AtomicBool,
+) {
+    // Seed from what's actually seated at startup so already-present boards
+    // are never reported as a hotplug insert, only genuine changes after
+    // this point are.
+    let mut occupied = extract_presence(read_presence());
+    let mut candidate = occupied;
+    let mut candidate_since = [None; SLOT_COUNT];
+
+    while !shutdown.load(Ordering::SeqCst) {
+        let presence = extract_presence(read_presence());
+
+        for slot in 0..SLOT_COUNT {
+            if presence[slot] != candidate[slot] {
+                candidate[slot] = presence[slot];
+                candidate_since[slot] = Some(Instant::now());
+                continue;
+            }
+
+            let stable = match candidate_since[slot] {
+                Some(since) => since.elapsed() >= DEBOUNCE,
+                None => false,
+            };
+
+            if !stable || presence[slot] == occupied[slot] {
+                continue;
+            }
+
+            occupied[slot] = presence[slot];
+            let event = if presence[slot] {
+                HotplugEvent::SlotInserted(slot)
+            } else {
+                HotplugEvent::SlotRemoved(slot)
+            };
+
+            // The receiver may have been dropped (daemon shutting down); the
+            // send error is ignored and the loop runs until `shutdown` is set.
+            let _ = tx.send(event);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn extract_presence(raw: u32) -> [bool; SLOT_COUNT] {
+    let mut presence = [false; SLOT_COUNT];
+    for (slot, present) in presence.iter_mut().enumerate() {
+        *present = raw & (1 << slot) != 0;
+    }
+    presence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_presence_reads_one_bit_per_slot() {
+        // Slot 0 and 2 present, slot 1 absent
+        assert_eq!(extract_presence(0b101), [true, false, true]);
+    }
+
+    #[test]
+    fn test_extract_presence_ignores_unrelated_bits() {
+        assert_eq!(extract_presence(0xFFFF_FFF8), [false, false, false]);
+    }
+
+    #[test]
+    fn test_boards_present_at_spawn_are_not_reported_as_inserts() {
+        let raw = Arc::new(std::sync::atomic::AtomicU32::new(0b011));
+        let reader_raw = Arc::clone(&raw);
+        let read_presence: PresenceReader =
+            Arc::new(move || reader_raw.load(Ordering::SeqCst));
+
+        let (monitor, rx) = HotplugMonitor::spawn(read_presence);
+        thread::sleep(DEBOUNCE * 2);
+        assert!(rx.try_recv().is_err(), "already-seated boards must not fire insert events");
+
+        // Slot 2 now plugged in: this *should* eventually fire.
+        raw.store(0b111, Ordering::SeqCst);
+        let event = rx.recv_timeout(DEBOUNCE * 4).expect("expected a hotplug event");
+        assert_eq!(event, HotplugEvent::SlotInserted(2));
+
+        monitor.stop();
+    }
+}
@@ -0,0 +1,127 @@
+//! I2C Master Driver
+//!
+//! Drives I2C transactions through the FPGA's I2C bridge (`regs::I2C_CTRL`)
+//! to reach the devices hanging off it: the PSU/PIC control MCU, hashboard
+//! EEPROMs, and the fan/temperature sensors.
+
+use crate::fpga::{regs, FpgaController};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Bit layout of `I2C_CTRL` (stock firmware format)
+mod ctrl {
+    /// Start the transaction on write; busy while the transaction is in flight (bit 31)
+    pub const START: u32 = 1 << 31;
+    /// Read (1) vs write (0) (bit 30)
+    pub const READ: u32 = 1 << 30;
+    /// 7-bit device address (bits 23:16)
+    pub const DEV_SHIFT: u32 = 16;
+    /// Register/command index (bits 15:8)
+    pub const REG_SHIFT: u32 = 8;
+    /// Data byte: write value in, read value out (bits 7:0)
+    pub const DATA_SHIFT: u32 = 0;
+}
+
+/// Maximum time to wait for the I2C bridge to clear its busy bit
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Delay between busy-bit polls
+const POLL_INTERVAL: Duration = Duration::from_micros(200);
+
+/// I2C bus error types
+#[derive(Debug)]
+pub enum I2cError {
+    /// The FPGA bridge never cleared its busy bit within `TIMEOUT`
+    Timeout,
+}
+
+impl std::fmt::Display for I2cError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "I2C transaction timed out waiting for FPGA bridge"),
+        }
+    }
+}
+
+impl std::error::Error for I2cError {}
+
+/// I2C bus reached through the FPGA's I2C bridge register
+///
+/// Borrows the `FpgaController` for the duration of the transaction(s) since
+/// every operation writes a command word to `I2C_CTRL` and then polls it.
+pub struct I2cBus<'a> {
+    fpga: &'a mut FpgaController,
+}
+
+impl<'a> I2cBus<'a> {
+    /// Wrap an existing FPGA controller to drive its I2C bridge
+    pub fn new(fpga: &'a mut FpgaController) -> Self {
+        Self { fpga }
+    }
+
+    /// Write a command word, set the start/busy bit, and spin until the
+    /// bridge reports done or `TIMEOUT` elapses.
+    fn transact(&mut self, command: u32) -> Result<u32, I2cError> {
+        self.fpga.write_reg(regs::I2C_CTRL, command | ctrl::START);
+
+        let started = Instant::now();
+        loop {
+            let status = self.fpga.read_reg(regs::I2C_CTRL);
+            if status & ctrl::START == 0 {
+                return Ok(status);
+            }
+            if started.elapsed() > TIMEOUT {
+                return Err(I2cError::Timeout);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Read a single byte from `reg` on device `dev`
+    pub fn read_byte(&mut self, dev: u8, reg: u8) -> Result<u8, I2cError> {
+        let command =
+            ctrl::READ | ((dev as u32) << ctrl::DEV_SHIFT) | ((reg as u32) << ctrl::REG_SHIFT);
+        let status = self.transact(command)?;
+        Ok((status >> ctrl::DATA_SHIFT) as u8)
+    }
+
+    /// Write a single byte to `reg` on device `dev`
+    pub fn write_byte(&mut self, dev: u8, reg: u8, val: u8) -> Result<(), I2cError> {
+        let command = ((dev as u32) << ctrl::DEV_SHIFT)
+            | ((reg as u32) << ctrl::REG_SHIFT)
+            | ((val as u32) << ctrl::DATA_SHIFT);
+        self.transact(command)?;
+        Ok(())
+    }
+
+    /// Blocking sequential read of `buf.len()` bytes starting at `start_reg`
+    ///
+    /// Mirrors the EEPROM read flow used to pull serial number and
+    /// voltage-domain data out of a hashboard's I2C EEPROM: each byte is its
+    /// own transaction, with the register index auto-incrementing between
+    /// calls.
+    pub fn read_eeprom_sequential(
+        &mut self,
+        dev: u8,
+        start_reg: u8,
+        buf: &mut [u8],
+    ) -> Result<(), I2cError> {
+        for (i, slot) in buf.iter_mut().enumerate() {
+            let reg = start_reg.wrapping_add(i as u8);
+            *slot = self.read_byte(dev, reg)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_word_layout() {
+        let command =
+            ctrl::READ | (0x50u32 << ctrl::DEV_SHIFT) | (0x02u32 << ctrl::REG_SHIFT);
+        assert_eq!(command, 0x4050_0200);
+    }
+}
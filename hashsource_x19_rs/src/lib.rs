@@ -2,6 +2,17 @@
 //!
 //! Shared library for FPGA, I2C, GPIO, and ASIC control on Bitmain Antminer X19 miners.
 
+pub mod bitstream;
+pub mod config;
 pub mod fpga;
+pub mod hotplug;
+pub mod i2c;
+pub mod register;
+pub mod thermal;
 
+pub use bitstream::{Bitstream, BitstreamError, ConfigPort};
+pub use config::{ConfigError, MinerConfig};
 pub use fpga::{FpgaController, FpgaError};
+pub use hotplug::{HotplugEvent, HotplugMonitor};
+pub use i2c::{I2cBus, I2cError};
+pub use thermal::{FanController, FanControllerConfig};
@@ -0,0 +1,246 @@
+//! Closed-Loop Thermal Control
+//!
+//! Drives the fan PWM channels (via [`FpgaController::set_channel_duty`])
+//! from a discrete PID loop over
+//! temperature readings (e.g. from the I2C sensor API in [`crate::i2c`]),
+//! with a configurable minimum duty floor and a latched hard thermal-trip
+//! cutoff so a runaway board always ends up at full fan speed rather than
+//! idle. A stalled fan (per [`FpgaController::detect_stall`]) latches the
+//! same hard trip.
+
+use crate::fpga::FpgaController;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// Tunable gains and setpoints for [`FanController`]
+#[derive(Debug, Clone, Copy)]
+pub struct FanControllerConfig {
+    /// Setpoint the loop tries to hold, in degrees C
+    pub target_temp_c: f32,
+    /// Proportional gain
+    pub kp: f32,
+    /// Integral gain
+    pub ki: f32,
+    /// Derivative gain (applied to the measurement, not the error)
+    pub kd: f32,
+    /// Lower clamp on the PID output, to avoid a fan stalling (channel
+    /// overrides are not clamped to it)
+    pub min_duty_percent: u8,
+    /// Upper clamp on the PID output (a thermal trip still forces 100%, and
+    /// channel overrides are not clamped to it)
+    pub max_duty_percent: u8,
+    /// Measured temperature at or above which the loop latches to 100% and
+    /// flags a fault, regardless of gains
+    pub trip_temp_c: f32,
+    /// Delay between control loop ticks
+    pub sample_interval: Duration,
+    /// Per-channel duty override; `None` means follow the PID output. A
+    /// thermal trip still forces every channel to 100% regardless of these.
+    pub channel_overrides: [Option<u8>; FpgaController::FAN_CHANNELS],
+}
+
+impl Default for FanControllerConfig {
+    fn default() -> Self {
+        Self {
+            target_temp_c: 65.0,
+            kp: 4.0,
+            ki: 0.5,
+            kd: 1.0,
+            min_duty_percent: 20,
+            max_duty_percent: 100,
+            trip_temp_c: 95.0,
+            sample_interval: Duration::from_secs(1),
+            channel_overrides: [None; FpgaController::FAN_CHANNELS],
+        }
+    }
+}
+
+/// Discrete PID fan speed controller with a latched thermal-trip cutoff
+pub struct FanController {
+    config: FanControllerConfig,
+    integral: f32,
+    last_measured: Option<f32>,
+    tripped: bool,
+}
+
+impl FanController {
+    /// Build a controller from the given gains/setpoints
+    pub fn new(config: FanControllerConfig) -> Self {
+        Self {
+            config,
+            integral: 0.0,
+            last_measured: None,
+            tripped: false,
+        }
+    }
+
+    /// Build a controller from the persisted [`crate::config::MinerConfig`],
+    /// falling back to defaults if none has been saved yet. Use this at
+    /// startup instead of [`FanControllerConfig::default`] directly so
+    /// tuning survives a reboot.
+    pub fn from_store() -> Self {
+        Self::new(crate::config::MinerConfig::load().unwrap_or_default().to_fan_config())
+    }
+
+    /// The configuration this controller is running with
+    pub fn config(&self) -> &FanControllerConfig {
+        &self.config
+    }
+
+    /// Whether the hard thermal-trip fault is latched
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+
+    /// Clear a latched thermal trip once the overheat condition has been
+    /// investigated and resolved
+    pub fn reset_trip(&mut self) {
+        self.tripped = false;
+        self.integral = 0.0;
+    }
+
+    /// Duty cycle to fall back to when the control loop stops running
+    pub fn safe_default_duty(&self) -> u8 {
+        self.config.min_duty_percent.max(50)
+    }
+
+    /// Compute the next duty cycle (0-100%) for a fresh temperature reading,
+    /// without touching the FPGA. Exposed separately from [`Self::tick`] so
+    /// the control law can be exercised without hardware.
+    fn compute(&mut self, measured_c: f32) -> u8 {
+        if measured_c >= self.config.trip_temp_c {
+            self.tripped = true;
+        }
+        if self.tripped {
+            return 100;
+        }
+
+        // Direct-acting: duty should rise when we're hotter than target.
+        let error = measured_c - self.config.target_temp_c;
+
+        // Derivative of the measurement, not the error, to avoid "derivative
+        // kick" on setpoint changes (the two are equivalent while the
+        // setpoint is held constant, since d(error)/dt = d(measured)/dt).
+        let derivative = match self.last_measured {
+            Some(prev) => measured_c - prev,
+            None => 0.0,
+        };
+        self.last_measured = Some(measured_c);
+
+        // Anti-windup: provisionally integrate, then only commit the new
+        // integral term if it didn't push the output into saturation.
+        let candidate_integral = self.integral + error;
+        let output =
+            self.config.kp * error + self.config.ki * candidate_integral + self.config.kd * derivative;
+        let clamped = output.clamp(
+            self.config.min_duty_percent as f32,
+            self.config.max_duty_percent as f32,
+        );
+        if clamped == output {
+            self.integral = candidate_integral;
+        }
+
+        clamped.round() as u8
+    }
+
+    /// Run one control-loop tick given a fresh temperature reading, and
+    /// drive `fpga` to the resulting duty cycle. A channel with a
+    /// [`FanControllerConfig::channel_overrides`] entry is pinned to that
+    /// duty instead of following the PID output (still overridden to 100%
+    /// while a thermal trip is latched). Also checks the duties commanded on
+    /// this tick against [`FpgaController::detect_stall`] and latches
+    /// the same hard-trip fault a thermal trip would, so a failed fan drives
+    /// every other channel to 100% rather than letting the board cook while
+    /// the control loop keeps trusting a duty cycle that never reached the
+    /// blades.
+    pub fn tick(&mut self, measured_c: f32, fpga: &mut FpgaController) -> u8 {
+        let duty = self.compute(measured_c);
+
+        let mut commanded = [0u8; FpgaController::FAN_CHANNELS];
+        for (index, override_duty) in self.config.channel_overrides.into_iter().enumerate() {
+            let channel_duty = if self.tripped {
+                duty
+            } else {
+                override_duty.unwrap_or(duty)
+            };
+            commanded[index] = channel_duty;
+            let _ = fpga.set_channel_duty(index, channel_duty);
+        }
+
+        let any_stalled = fpga
+            .detect_stall(commanded)
+            .is_ok_and(|stalled| !stalled.is_empty());
+        if any_stalled {
+            self.tripped = true;
+        }
+
+        duty
+    }
+
+    /// Run the control loop until `shutdown` is set, sampling `read_temp`
+    /// every `sample_interval`, then restore [`Self::safe_default_duty`]
+    /// before returning. `shutdown` is meant to be the same
+    /// `AtomicBool` a `SIGINT`/`SIGTERM` handler flips, matching the pattern
+    /// used by the fan ramp example.
+    pub fn run(
+        &mut self,
+        fpga: &mut FpgaController,
+        shutdown: &AtomicBool,
+        mut read_temp: impl FnMut() -> f32,
+    ) {
+        while !shutdown.load(Ordering::SeqCst) {
+            let measured = read_temp();
+            self.tick(measured, fpga);
+            thread::sleep(self.config.sample_interval);
+        }
+
+        fpga.set_fan_speed(self.safe_default_duty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> FanControllerConfig {
+        FanControllerConfig {
+            target_temp_c: 60.0,
+            kp: 2.0,
+            ki: 0.0,
+            kd: 0.0,
+            min_duty_percent: 20,
+            max_duty_percent: 100,
+            trip_temp_c: 90.0,
+            sample_interval: Duration::from_millis(1),
+            channel_overrides: [None; FpgaController::FAN_CHANNELS],
+        }
+    }
+
+    #[test]
+    fn test_hotter_than_target_increases_duty() {
+        let mut fan = FanController::new(test_config());
+        let duty = fan.compute(80.0);
+        assert!(duty > fan.config().min_duty_percent);
+    }
+
+    #[test]
+    fn test_never_below_min_floor() {
+        let mut fan = FanController::new(test_config());
+        let duty = fan.compute(10.0);
+        assert_eq!(duty, fan.config().min_duty_percent);
+    }
+
+    #[test]
+    fn test_thermal_trip_latches_full_speed() {
+        let mut fan = FanController::new(test_config());
+        assert_eq!(fan.compute(95.0), 100);
+        assert!(fan.is_tripped());
+
+        // Stays latched even after temperature drops back down
+        assert_eq!(fan.compute(40.0), 100);
+
+        fan.reset_trip();
+        assert!(!fan.is_tripped());
+    }
+}